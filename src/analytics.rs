@@ -0,0 +1,273 @@
+use regex::Regex;
+use select::document::Document;
+use select::predicate::Name;
+
+/// One detected analytics/tag-manager vendor, along with whatever
+/// identifier (measurement ID, container ID, site ID, ...) was found for
+/// it, so the audit can say "GA4 present via GTM-ABC123" instead of just
+/// "analytics: yes".
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyticsMatch {
+    pub vendor: &'static str,
+    pub identifier: String,
+}
+
+/// Scans every `<script>` tag's `src` and inline body (concatenated, since
+/// vendors are identified the same way in either place) for known analytics
+/// and tag-manager signatures.
+pub fn detect(html: &str) -> Vec<AnalyticsMatch> {
+    let document = Document::from(html);
+    let mut text = String::new();
+    let mut plausible_domain = None;
+    let mut fathom_site = None;
+
+    for node in document.find(Name("script")) {
+        if let Some(src) = node.attr("src") {
+            text.push_str(src);
+            text.push('\n');
+
+            if src.contains("plausible.io") {
+                plausible_domain = node.attr("data-domain").map(|d| d.to_string());
+            }
+            if src.contains("usefathom.com") {
+                fathom_site = node.attr("data-site").map(|d| d.to_string());
+            }
+        }
+        text.push_str(&node.text());
+        text.push('\n');
+    }
+
+    let mut matches = Vec::new();
+
+    if let Some(id) = first_match(&text, r"G-[A-Z0-9]{6,}") {
+        matches.push(AnalyticsMatch {
+            vendor: "Google Analytics 4",
+            identifier: id,
+        });
+    }
+    if let Some(id) = first_match(&text, r"UA-\d+-\d+") {
+        matches.push(AnalyticsMatch {
+            vendor: "Universal Analytics (legacy)",
+            identifier: id,
+        });
+    }
+    if let Some(id) = first_match(&text, r"GTM-[A-Z0-9]+") {
+        matches.push(AnalyticsMatch {
+            vendor: "Google Tag Manager",
+            identifier: id,
+        });
+    } else if text.contains("googletagmanager.com/gtm.js") {
+        matches.push(AnalyticsMatch {
+            vendor: "Google Tag Manager",
+            identifier: "googletagmanager.com/gtm.js".to_string(),
+        });
+    }
+    if text.contains("fbq(") || text.contains("connect.facebook.net") {
+        let id = first_capture(&text, r"fbq\(\s*'init'\s*,\s*'(\d+)'").unwrap_or_else(|| "fbq(...)".to_string());
+        matches.push(AnalyticsMatch {
+            vendor: "Meta (Facebook) Pixel",
+            identifier: id,
+        });
+    }
+    if text.contains("ttq.load") || text.contains("analytics.tiktok.com") {
+        let id = first_capture(&text, r"ttq\.load\(\s*'([A-Za-z0-9]+)'").unwrap_or_else(|| "ttq.load(...)".to_string());
+        matches.push(AnalyticsMatch {
+            vendor: "TikTok Pixel",
+            identifier: id,
+        });
+    }
+    if let Some(id) = first_capture(&text, r"clarity\.ms/tag/([A-Za-z0-9]+)") {
+        matches.push(AnalyticsMatch {
+            vendor: "Microsoft Clarity",
+            identifier: id,
+        });
+    }
+    if let Some(domain) = plausible_domain {
+        matches.push(AnalyticsMatch {
+            vendor: "Plausible",
+            identifier: domain,
+        });
+    } else if text.contains("plausible.io") {
+        matches.push(AnalyticsMatch {
+            vendor: "Plausible",
+            identifier: "plausible.io/js/script.js".to_string(),
+        });
+    }
+    if text.contains("matomo.js") || text.contains("piwik.js") {
+        let id = first_capture(&text, r#"setSiteId['"]?\s*,\s*'?(\d+)"#).unwrap_or_else(|| "matomo.js".to_string());
+        matches.push(AnalyticsMatch {
+            vendor: "Matomo",
+            identifier: id,
+        });
+    }
+    if let Some(site) = fathom_site {
+        matches.push(AnalyticsMatch {
+            vendor: "Fathom",
+            identifier: site,
+        });
+    } else if text.contains("usefathom.com") {
+        matches.push(AnalyticsMatch {
+            vendor: "Fathom",
+            identifier: "usefathom.com/script.js".to_string(),
+        });
+    }
+
+    matches
+}
+
+fn first_match(text: &str, pattern: &str) -> Option<String> {
+    Regex::new(pattern)
+        .ok()?
+        .find(text)
+        .map(|m| m.as_str().to_string())
+}
+
+fn first_capture(text: &str, pattern: &str) -> Option<String> {
+    Regex::new(pattern)
+        .ok()?
+        .captures(text)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_no_analytics() {
+        let html = "<html><head></head><body>hello</body></html>";
+        assert_eq!(detect(html), Vec::new());
+    }
+
+    #[test]
+    fn detect_single_vendor_cases() {
+        let cases = [
+            (
+                r#"<script>gtag('config', 'G-ABC1234')</script>"#,
+                AnalyticsMatch { vendor: "Google Analytics 4", identifier: "G-ABC1234".to_string() },
+            ),
+            (
+                r#"<script>ga('create', 'UA-12345-6')</script>"#,
+                AnalyticsMatch { vendor: "Universal Analytics (legacy)", identifier: "UA-12345-6".to_string() },
+            ),
+            (
+                r#"<script src="https://analytics.tiktok.com/i18n/pixel/events.js"></script><script>ttq.load('ABC123XYZ')</script>"#,
+                AnalyticsMatch { vendor: "TikTok Pixel", identifier: "ABC123XYZ".to_string() },
+            ),
+            (
+                r#"<script src="https://www.clarity.ms/tag/abcDEF123"></script>"#,
+                AnalyticsMatch { vendor: "Microsoft Clarity", identifier: "abcDEF123".to_string() },
+            ),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(detect(html), vec![expected], "html = {:?}", html);
+        }
+    }
+
+    #[test]
+    fn detect_gtm_takes_priority_over_bare_container_script() {
+        // Both the container-id regex and the bare `gtm.js` URL match here;
+        // the explicit container id should win rather than the two stacking
+        // into two separate "Google Tag Manager" matches.
+        let html = r#"<script src="https://www.googletagmanager.com/gtm.js?id=GTM-ABCD12"></script>"#;
+        assert_eq!(
+            detect(html),
+            vec![AnalyticsMatch { vendor: "Google Tag Manager", identifier: "GTM-ABCD12".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_gtm_without_container_id_falls_back_to_script_url() {
+        let html = r#"<script src="https://www.googletagmanager.com/gtm.js"></script>"#;
+        assert_eq!(
+            detect(html),
+            vec![AnalyticsMatch {
+                vendor: "Google Tag Manager",
+                identifier: "googletagmanager.com/gtm.js".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn detect_ga4_and_gtm_both_present_are_not_confused() {
+        // `GTM-[A-Z0-9]+` and `G-[A-Z0-9]{6,}` are similar enough prefixes
+        // that a sloppy regex could cross-match; confirm each is attributed
+        // to its own vendor when both appear together.
+        let html = r#"<script>gtag('config', 'G-ABC1234'); gtag('config', 'GTM-WXYZ99')</script>"#;
+        let matches = detect(html);
+        assert_eq!(matches.len(), 2);
+        assert!(matches.contains(&AnalyticsMatch { vendor: "Google Analytics 4", identifier: "G-ABC1234".to_string() }));
+        assert!(matches.contains(&AnalyticsMatch { vendor: "Google Tag Manager", identifier: "GTM-WXYZ99".to_string() }));
+    }
+
+    #[test]
+    fn detect_facebook_pixel_with_and_without_captured_id() {
+        let with_id = r#"<script>fbq('init', '123456789012345');</script>"#;
+        assert_eq!(
+            detect(with_id),
+            vec![AnalyticsMatch { vendor: "Meta (Facebook) Pixel", identifier: "123456789012345".to_string() }]
+        );
+
+        let without_capturable_id = r#"<script src="https://connect.facebook.net/en_US/fbevents.js"></script>"#;
+        assert_eq!(
+            detect(without_capturable_id),
+            vec![AnalyticsMatch { vendor: "Meta (Facebook) Pixel", identifier: "fbq(...)".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_plausible_captures_data_domain_attribute() {
+        let html = r#"<script defer data-domain="example.com" src="https://plausible.io/js/script.js"></script>"#;
+        assert_eq!(
+            detect(html),
+            vec![AnalyticsMatch { vendor: "Plausible", identifier: "example.com".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_plausible_without_data_domain_falls_back() {
+        let html = r#"<script src="https://plausible.io/js/script.js"></script>"#;
+        assert_eq!(
+            detect(html),
+            vec![AnalyticsMatch { vendor: "Plausible", identifier: "plausible.io/js/script.js".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_fathom_captures_data_site_attribute() {
+        let html = r#"<script src="https://cdn.usefathom.com/script.js" data-site="ABCDEFGH"></script>"#;
+        assert_eq!(
+            detect(html),
+            vec![AnalyticsMatch { vendor: "Fathom", identifier: "ABCDEFGH".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_matomo_captures_site_id_with_or_without_quotes() {
+        let quoted = r#"<script>_paq.push(['setSiteId', '3']); var u="//analytics.example.com/"; _paq.push(['setTrackerUrl', u+'matomo.js']);</script>"#;
+        assert_eq!(
+            detect(quoted),
+            vec![AnalyticsMatch { vendor: "Matomo", identifier: "3".to_string() }]
+        );
+
+        let unquoted = r#"<script>_paq.push(['setSiteId', 7]); _paq.push(['setTrackerUrl', 'https://example.com/piwik.js']);</script>"#;
+        assert_eq!(
+            detect(unquoted),
+            vec![AnalyticsMatch { vendor: "Matomo", identifier: "7".to_string() }]
+        );
+    }
+
+    #[test]
+    fn detect_multiple_vendors_on_one_page() {
+        let html = r#"
+            <script src="https://www.googletagmanager.com/gtm.js?id=GTM-ABCD12"></script>
+            <script>fbq('init', '999888777666555');</script>
+        "#;
+        let matches = detect(html);
+        assert_eq!(matches.len(), 2);
+        assert!(matches.contains(&AnalyticsMatch { vendor: "Google Tag Manager", identifier: "GTM-ABCD12".to_string() }));
+        assert!(matches.contains(&AnalyticsMatch { vendor: "Meta (Facebook) Pixel", identifier: "999888777666555".to_string() }));
+    }
+}
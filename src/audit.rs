@@ -0,0 +1,366 @@
+use std::time::Duration;
+
+use reqwest::Client;
+use select::document::Document;
+use serde::Serialize;
+
+use url::Url;
+
+use crate::redirects::RedirectAnalysis;
+use crate::{get_canonical, has_amp, has_schema_markup, is_responsive, is_valid_robots_txt};
+
+/// How long a single-page audit may spend fetching the site's robots.txt and
+/// checking for a sitemap, mirroring the crawler's own `TIME_LIMIT` so a slow
+/// or hanging site can't stall the audit indefinitely.
+const SITE_CONTEXT_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Site-wide facts that apply to every page on a site, fetched once per site
+/// rather than once per page. During a crawl these are computed a single
+/// time in `crawl_site` and reused for each page it visits; outside a crawl,
+/// [`SiteContext::for_site`] derives them from the one URL being audited.
+pub struct SiteContext {
+    pub robots_txt: Option<String>,
+    pub sitemap_found: bool,
+}
+
+impl SiteContext {
+    /// Fetches `base_url`'s robots.txt and looks for a real, parseable
+    /// sitemap (following `Sitemap:` lines in robots.txt, sitemap index
+    /// expansion, and gzip, same as the crawler), bounding both under
+    /// [`SITE_CONTEXT_TIME_LIMIT`].
+    pub async fn for_site(client: &Client, base_url: &str) -> Self {
+        let robots_txt = tokio::time::timeout(SITE_CONTEXT_TIME_LIMIT, crate::get_robots_txt(client, base_url))
+            .await
+            .unwrap_or(None);
+
+        let sitemap_found = match Url::parse(base_url) {
+            Ok(parsed) => {
+                let locations = crate::sitemap::discover_sitemap_locations(&parsed, robots_txt.as_deref());
+                tokio::time::timeout(SITE_CONTEXT_TIME_LIMIT, crate::sitemap::fetch_all_sitemap_urls(client, locations))
+                    .await
+                    .map(|urls| !urls.is_empty())
+                    .unwrap_or(false)
+            }
+            Err(_) => false,
+        };
+
+        SiteContext { robots_txt, sitemap_found }
+    }
+}
+
+/// Pass/warn/fail verdict for a single `Auditor`, modeled after the
+/// extractor-result pattern in the `scrape` crate: every check reports the
+/// same shape so callers (human-readable printing, JSON export) don't need
+/// to special-case individual checks.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditResult {
+    pub id: String,
+    pub severity: Severity,
+    pub detail: String,
+    pub evidence: Vec<String>,
+}
+
+/// Everything an `Auditor` needs to examine a single fetched page. Any I/O
+/// (fetching robots.txt, checking for a sitemap) happens once up front in
+/// [`build_page_context`] so that `Auditor::audit` itself can stay
+/// synchronous.
+pub struct PageContext<'a> {
+    pub client: &'a Client,
+    pub url: String,
+    pub html: String,
+    pub document: Document,
+    pub robots_txt: Option<String>,
+    pub sitemap_found: bool,
+    pub redirect_analysis: Option<RedirectAnalysis>,
+}
+
+/// Builds the context auditors run against. `redirect_analysis` is taken as
+/// a parameter rather than derived here because by the time this is called
+/// during a crawl, `url` is already the crawler's resolved destination —
+/// any redirect chain has already been walked and consumed by `crawl_site`,
+/// so re-deriving it from `url` would always see a direct, non-redirecting
+/// hit. Callers that don't already have crawler-observed hops (the
+/// single-page path) fetch their own via [`analyze_redirects_fresh`].
+pub async fn build_page_context<'a>(
+    client: &'a Client,
+    url: &str,
+    html: &str,
+    site: &SiteContext,
+    redirect_analysis: Option<RedirectAnalysis>,
+) -> PageContext<'a> {
+    PageContext {
+        client,
+        url: url.to_string(),
+        html: html.to_string(),
+        document: Document::from(html),
+        robots_txt: site.robots_txt.clone(),
+        sitemap_found: site.sitemap_found,
+        redirect_analysis,
+    }
+}
+
+/// Walks the redirect chain starting fresh from `url`. Only meaningful when
+/// no redirect hops have already been observed for this URL elsewhere (i.e.
+/// outside a crawl); see [`build_page_context`].
+pub async fn analyze_redirects_fresh(url: &str) -> Option<RedirectAnalysis> {
+    let start_url = Url::parse(url).ok()?;
+    let redirect_client = crate::redirects::build_redirect_client().ok()?;
+    crate::redirects::analyze(&redirect_client, &start_url).await.ok()
+}
+
+/// A single, self-contained audit check. Each existing boolean check (schema
+/// markup, AMP, canonical tag, ...) gets its own `Auditor` impl instead of
+/// writing straight into a loosely-typed map.
+pub trait Auditor {
+    fn name(&self) -> &str;
+    fn audit(&self, ctx: &PageContext) -> AuditResult;
+}
+
+pub fn default_auditors() -> Vec<Box<dyn Auditor>> {
+    vec![
+        Box::new(SchemaMarkupAuditor),
+        Box::new(AmpAuditor),
+        Box::new(CanonicalAuditor),
+        Box::new(ResponsiveAuditor),
+        Box::new(AnalyticsAuditor),
+        Box::new(RobotsAuditor),
+        Box::new(SitemapAuditor),
+        Box::new(RedirectAuditor),
+    ]
+}
+
+pub fn run_checks(auditors: &[Box<dyn Auditor>], ctx: &PageContext) -> Vec<AuditResult> {
+    auditors.iter().map(|auditor| auditor.audit(ctx)).collect()
+}
+
+struct SchemaMarkupAuditor;
+impl Auditor for SchemaMarkupAuditor {
+    fn name(&self) -> &str {
+        "schema_markup"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        let found = has_schema_markup(&ctx.html);
+        AuditResult {
+            id: self.name().to_string(),
+            severity: if found { Severity::Pass } else { Severity::Warn },
+            detail: if found {
+                "Structured data (JSON-LD, Microdata, or RDFa) was found".to_string()
+            } else {
+                "No structured data markup was found".to_string()
+            },
+            evidence: Vec::new(),
+        }
+    }
+}
+
+struct AmpAuditor;
+impl Auditor for AmpAuditor {
+    fn name(&self) -> &str {
+        "amp"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        let found = has_amp(&ctx.html);
+        AuditResult {
+            id: self.name().to_string(),
+            severity: Severity::Pass,
+            detail: if found {
+                "An AMP version of this page was found".to_string()
+            } else {
+                "No AMP version of this page was found".to_string()
+            },
+            evidence: Vec::new(),
+        }
+    }
+}
+
+struct CanonicalAuditor;
+impl Auditor for CanonicalAuditor {
+    fn name(&self) -> &str {
+        "canonical"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        match get_canonical(&ctx.html) {
+            Some(href) => AuditResult {
+                id: self.name().to_string(),
+                severity: Severity::Pass,
+                detail: "A canonical tag was found".to_string(),
+                evidence: vec![href],
+            },
+            None => AuditResult {
+                id: self.name().to_string(),
+                severity: Severity::Warn,
+                detail: "No canonical tag was found".to_string(),
+                evidence: Vec::new(),
+            },
+        }
+    }
+}
+
+struct ResponsiveAuditor;
+impl Auditor for ResponsiveAuditor {
+    fn name(&self) -> &str {
+        "responsive"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        let responsive = is_responsive(&ctx.html);
+        AuditResult {
+            id: self.name().to_string(),
+            severity: if responsive { Severity::Pass } else { Severity::Fail },
+            detail: if responsive {
+                "A viewport meta tag was found".to_string()
+            } else {
+                "No viewport meta tag was found".to_string()
+            },
+            evidence: Vec::new(),
+        }
+    }
+}
+
+struct AnalyticsAuditor;
+impl Auditor for AnalyticsAuditor {
+    fn name(&self) -> &str {
+        "analytics"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        let found = crate::analytics::detect(&ctx.html);
+        AuditResult {
+            id: self.name().to_string(),
+            severity: if found.is_empty() { Severity::Warn } else { Severity::Pass },
+            detail: if found.is_empty() {
+                "No analytics or tag-manager script was found".to_string()
+            } else {
+                let vendors: Vec<&str> = found.iter().map(|m| m.vendor).collect();
+                format!("Detected: {}", vendors.join(", "))
+            },
+            evidence: found
+                .iter()
+                .map(|m| format!("{}: {}", m.vendor, m.identifier))
+                .collect(),
+        }
+    }
+}
+
+struct RobotsAuditor;
+impl Auditor for RobotsAuditor {
+    fn name(&self) -> &str {
+        "robots_txt"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        match &ctx.robots_txt {
+            Some(body) if is_valid_robots_txt(body) => AuditResult {
+                id: self.name().to_string(),
+                severity: Severity::Pass,
+                detail: "robots.txt was found and is well-formed".to_string(),
+                evidence: Vec::new(),
+            },
+            Some(_) => AuditResult {
+                id: self.name().to_string(),
+                severity: Severity::Warn,
+                detail: "robots.txt was found but contains unrecognized directives".to_string(),
+                evidence: Vec::new(),
+            },
+            None => AuditResult {
+                id: self.name().to_string(),
+                severity: Severity::Warn,
+                detail: "No robots.txt was found".to_string(),
+                evidence: Vec::new(),
+            },
+        }
+    }
+}
+
+struct SitemapAuditor;
+impl Auditor for SitemapAuditor {
+    fn name(&self) -> &str {
+        "sitemap"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        AuditResult {
+            id: self.name().to_string(),
+            severity: if ctx.sitemap_found { Severity::Pass } else { Severity::Warn },
+            detail: if ctx.sitemap_found {
+                "sitemap.xml was found".to_string()
+            } else {
+                "No sitemap.xml was found".to_string()
+            },
+            evidence: Vec::new(),
+        }
+    }
+}
+
+struct RedirectAuditor;
+impl Auditor for RedirectAuditor {
+    fn name(&self) -> &str {
+        "redirects"
+    }
+
+    fn audit(&self, ctx: &PageContext) -> AuditResult {
+        let analysis = match &ctx.redirect_analysis {
+            Some(analysis) => analysis,
+            None => {
+                return AuditResult {
+                    id: self.name().to_string(),
+                    severity: Severity::Warn,
+                    detail: "Could not walk the redirect chain".to_string(),
+                    evidence: Vec::new(),
+                }
+            }
+        };
+
+        let mut evidence: Vec<String> = analysis
+            .hops
+            .iter()
+            .map(|hop| format!("{} {}", hop.status, hop.url))
+            .collect();
+        evidence.extend(analysis.mixed_content.iter().map(|url| format!("mixed content: {}", url)));
+
+        let mut issues = Vec::new();
+        if analysis.has_long_chain() {
+            issues.push(format!("{} redirect hop(s) before reaching the final page", analysis.hop_count()));
+        }
+        if !analysis.mixed_content.is_empty() {
+            issues.push(format!("{} insecure (http://) subresource(s) on an https page", analysis.mixed_content.len()));
+        }
+
+        let detail = if !issues.is_empty() {
+            issues.join("; ")
+        } else if analysis.upgraded_to_https {
+            "Single hop, upgraded from http to https".to_string()
+        } else {
+            "No redirects and no mixed content".to_string()
+        };
+
+        AuditResult {
+            id: self.name().to_string(),
+            severity: if issues.is_empty() { Severity::Pass } else { Severity::Warn },
+            detail,
+            evidence,
+        }
+    }
+}
+
+/// The full audit for one page: the typed pass/warn/fail checks plus
+/// everything that hasn't been migrated to an `Auditor` yet (load time,
+/// broken links, indexing status, ...). `#[derive(Serialize)]` lets
+/// `--format json` dump the whole thing as-is.
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    pub url: String,
+    pub checks: Vec<AuditResult>,
+    pub details: std::collections::HashMap<String, Vec<String>>,
+}
@@ -0,0 +1,285 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode};
+use select::document::Document;
+use select::predicate::{Attr, Name, Predicate};
+use url::Url;
+
+use crate::audit_page;
+use crate::redirects::{self, RedirectAnalysis, RedirectHop};
+use crate::robots::{self, RobotsGate};
+use crate::sitemap;
+
+/// Quickpeep-style safety budgets: one slow or oversized page can't be
+/// allowed to stall (or blow out the memory of) a whole crawl.
+const SIZE_LIMIT: usize = 4 * 1024 * 1024;
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+pub struct CrawlConfig {
+    pub max_pages: usize,
+    pub max_depth: usize,
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        CrawlConfig {
+            max_pages: 100,
+            max_depth: 5,
+        }
+    }
+}
+
+/// The result of fetching a single URL during a crawl. Modeling this as an
+/// enum (rather than a `Result<String, Error>`) lets the crawl loop react
+/// differently to a redirect, an oversized body, or a timeout instead of
+/// lumping them all together as "failed".
+pub enum FetchOutcome {
+    Page {
+        url: Url,
+        html: String,
+        status: StatusCode,
+        x_robots_tag: Option<String>,
+    },
+    Redirect { new_url: Url, status: StatusCode },
+    TooLarge,
+    Timeout,
+    Failure(String),
+}
+
+pub struct PageReport {
+    pub url: Url,
+    pub details: crate::audit::AuditReport,
+}
+
+pub struct SiteReport {
+    pub pages: Vec<PageReport>,
+    pub pages_skipped_too_large: usize,
+    pub pages_timed_out: usize,
+    pub pages_failed: usize,
+    pub pages_disallowed_by_robots: usize,
+    pub sitemap_coverage: sitemap::SitemapCoverage,
+}
+
+/// Builds the client used while crawling. Redirects are turned off so that
+/// `fetch_page` can surface them as a distinct `FetchOutcome::Redirect`
+/// rather than silently following them and losing the hop, and the whole
+/// request is bounded by `TIME_LIMIT` so a hanging connection can't stall
+/// `fetch_page`'s own timeout wrapper indefinitely.
+pub fn build_crawl_client() -> reqwest::Result<Client> {
+    Client::builder().redirect(Policy::none()).timeout(TIME_LIMIT).build()
+}
+
+/// Crawls `start_url` breadth-first, following same-domain links it finds on
+/// each page plus anything listed in `sitemap.xml`, until it runs out of
+/// frontier or hits `config.max_pages` / `config.max_depth`.
+pub async fn crawl_site(client: &Client, start_url: &Url, config: &CrawlConfig) -> SiteReport {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    visited.insert(normalize(start_url));
+    queue.push_back((start_url.clone(), 0usize, Vec::new()));
+
+    let robots_txt_body = crate::get_robots_txt(client, start_url.as_str()).await;
+    let sitemap_locations = sitemap::discover_sitemap_locations(start_url, robots_txt_body.as_deref());
+    let sitemap_urls = sitemap::fetch_all_sitemap_urls(client, sitemap_locations).await;
+    for sitemap_url in &sitemap_urls {
+        if visited.insert(normalize(sitemap_url)) {
+            queue.push_back((sitemap_url.clone(), 0, Vec::new()));
+        }
+    }
+
+    // `RobotsGate::for_site` does its own blocking HTTP fetch under the
+    // hood (via `robotparser`'s internal client), separate from — and not
+    // bounded by — `client`'s own `TIME_LIMIT`; a slow or black-holed
+    // robots.txt would otherwise hang the whole crawl on its very first
+    // request. Times out the same way every other site-wide fetch here
+    // does, failing open (allow everything) to match `RobotsGate`'s own
+    // fail-open semantics for a robots.txt that couldn't be read.
+    let robots_gate = tokio::time::timeout(TIME_LIMIT, RobotsGate::for_site(start_url))
+        .await
+        .unwrap_or_else(|_| RobotsGate::allow_all(start_url));
+
+    // Reuses the robots.txt body and sitemap listing already fetched above
+    // instead of re-deriving them per page: every page on the site shares
+    // the same robots.txt/sitemap, so there's nothing page-specific to gain
+    // by asking again, and asking from a non-root page's own URL (e.g.
+    // `.../blog/post-1/robots.txt`) would just 404.
+    let site_context = crate::audit::SiteContext {
+        robots_txt: robots_txt_body.clone(),
+        sitemap_found: !sitemap_urls.is_empty(),
+    };
+
+    let mut report = SiteReport {
+        pages: Vec::new(),
+        pages_skipped_too_large: 0,
+        pages_timed_out: 0,
+        pages_failed: 0,
+        pages_disallowed_by_robots: 0,
+        sitemap_coverage: sitemap::SitemapCoverage::default(),
+    };
+    let mut crawled_indexable_urls = Vec::new();
+
+    while let Some((url, depth, redirect_chain)) = queue.pop_front() {
+        if report.pages.len() >= config.max_pages {
+            break;
+        }
+
+        if !robots_gate.is_allowed(&url) {
+            report.pages_disallowed_by_robots += 1;
+            continue;
+        }
+
+        match fetch_page(client, &url).await {
+            FetchOutcome::Page { url, html, status, x_robots_tag } => {
+                let directives = robots::meta_robots_directives(&html)
+                    .merge(robots::header_robots_directives(x_robots_tag.as_deref()));
+
+                if !directives.nofollow && depth < config.max_depth {
+                    for link in same_domain_links(&html, &url) {
+                        if visited.insert(normalize(&link)) {
+                            queue.push_back((link, depth + 1, Vec::new()));
+                        }
+                    }
+                }
+
+                let redirect_analysis = build_redirect_analysis(redirect_chain, &url, status, &html);
+                let mut details = audit_page(client, url.as_str(), &html, &site_context, Some(redirect_analysis)).await;
+                if directives.noindex {
+                    details.details.insert("Index Pages".to_string(), vec![String::new()]);
+                    details.details.insert("Non Index Pages".to_string(), vec![url.to_string()]);
+                } else {
+                    crawled_indexable_urls.push(url.clone());
+                }
+
+                report.pages.push(PageReport { url, details });
+            }
+            FetchOutcome::Redirect { new_url, status } => {
+                if visited.insert(normalize(&new_url)) {
+                    let mut chain = redirect_chain;
+                    chain.push(RedirectHop { url: url.clone(), status });
+                    queue.push_back((new_url, depth, chain));
+                }
+            }
+            FetchOutcome::TooLarge => report.pages_skipped_too_large += 1,
+            FetchOutcome::Timeout => report.pages_timed_out += 1,
+            FetchOutcome::Failure(_) => report.pages_failed += 1,
+        }
+    }
+
+    report.sitemap_coverage = sitemap::diff_coverage(&sitemap_urls, &crawled_indexable_urls);
+    report
+}
+
+/// Turns the redirect hops the BFS loop already walked to reach `final_url`
+/// into a `RedirectAnalysis`, the same shape `redirects::analyze` would have
+/// produced had it walked the chain itself — so `RedirectAuditor` sees a real
+/// answer during a crawl instead of the single, non-redirecting hit it would
+/// see if it re-walked from the already-resolved `final_url`.
+fn build_redirect_analysis(
+    mut hops: Vec<RedirectHop>,
+    final_url: &Url,
+    final_status: StatusCode,
+    final_html: &str,
+) -> RedirectAnalysis {
+    let start_scheme = hops
+        .first()
+        .map(|hop| hop.url.scheme().to_string())
+        .unwrap_or_else(|| final_url.scheme().to_string());
+    hops.push(RedirectHop {
+        url: final_url.clone(),
+        status: final_status,
+    });
+
+    redirects::finish_analysis(hops, &start_scheme, final_url.clone(), final_html)
+}
+
+/// Fetches a single page, enforcing `TIME_LIMIT` for the whole request
+/// (connect + body) and `SIZE_LIMIT` on the response body.
+async fn fetch_page(client: &Client, url: &Url) -> FetchOutcome {
+    match tokio::time::timeout(TIME_LIMIT, fetch_page_inner(client, url)).await {
+        Ok(outcome) => outcome,
+        Err(_) => FetchOutcome::Timeout,
+    }
+}
+
+async fn fetch_page_inner(client: &Client, url: &Url) -> FetchOutcome {
+    let response = match client.get(url.clone()).send().await {
+        Ok(response) => response,
+        Err(err) => return FetchOutcome::Failure(err.to_string()),
+    };
+
+    if response.status().is_redirection() {
+        let status = response.status();
+        return match response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|location| url.join(location).ok())
+        {
+            Some(new_url) => FetchOutcome::Redirect { new_url, status },
+            None => FetchOutcome::Failure("redirect without a usable Location header".to_string()),
+        };
+    }
+
+    let status = response.status();
+    let x_robots_tag = response
+        .headers()
+        .get("x-robots-tag")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let body = match read_body_within_limit(response).await {
+        Some(body) => body,
+        None => return FetchOutcome::TooLarge,
+    };
+
+    match String::from_utf8(body) {
+        Ok(html) => FetchOutcome::Page {
+            url: url.clone(),
+            html,
+            status,
+            x_robots_tag,
+        },
+        Err(_) => FetchOutcome::Failure("response body was not valid UTF-8".to_string()),
+    }
+}
+
+async fn read_body_within_limit(mut response: reqwest::Response) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    while let Ok(Some(chunk)) = response.chunk().await {
+        body.extend_from_slice(&chunk);
+        if body.len() > SIZE_LIMIT {
+            return None;
+        }
+    }
+    Some(body)
+}
+
+/// Collects every `<a href>` on the page that resolves to the same host as
+/// `page_url`, so the crawl stays within the site being audited.
+fn same_domain_links(html: &str, page_url: &Url) -> Vec<Url> {
+    let document = Document::from(html);
+    let mut links = Vec::new();
+
+    for link in document.find(Name("a").and(Attr("href", ()))) {
+        if let Some(href) = link.attr("href") {
+            if let Ok(resolved) = page_url.join(href) {
+                if resolved.host_str() == page_url.host_str() && (resolved.scheme() == "http" || resolved.scheme() == "https") {
+                    links.push(resolved);
+                }
+            }
+        }
+    }
+
+    links
+}
+
+/// Strips the fragment (and trailing slash) from a URL so that `/foo`,
+/// `/foo/` and `/foo#section` are all treated as the same visited entry.
+fn normalize(url: &Url) -> String {
+    let mut normalized = url.clone();
+    normalized.set_fragment(None);
+    let s = normalized.as_str();
+    s.strip_suffix('/').unwrap_or(s).to_string()
+}
@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode};
+use select::document::Document;
+use select::predicate::{Attr, Name, Predicate};
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// How many links we'll check at once. Large sites have hundreds of `<a>` tags
+/// per page, so checking them one at a time (the old behavior) made a full
+/// audit take minutes; a bounded semaphore keeps us polite to the target
+/// server while still running well ahead of serial checks.
+const MAX_CONCURRENT_CHECKS: usize = 16;
+
+/// We only care whether a link eventually resolves, not whether it bounces
+/// through a redirect chain first, so a handful of hops is enough before we
+/// give up and call it broken.
+const MAX_REDIRECTS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkStatus {
+    Ok(StatusCode),
+    Redirected { final_status: StatusCode },
+    ClientError(StatusCode),
+    ServerError(StatusCode),
+    Timeout,
+    DnsError,
+    Other(String),
+}
+
+impl LinkStatus {
+    pub fn is_broken(&self) -> bool {
+        matches!(
+            self,
+            LinkStatus::ClientError(_)
+                | LinkStatus::ServerError(_)
+                | LinkStatus::Timeout
+                | LinkStatus::DnsError
+                | LinkStatus::Other(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkCheckResult {
+    /// The href exactly as it appeared in the document.
+    pub href: String,
+    /// `href` resolved against the page's base URL.
+    pub resolved_url: Url,
+    pub status: LinkStatus,
+    /// The URL we actually landed on, if it differs from `resolved_url`.
+    pub redirect_chain: Vec<Url>,
+}
+
+/// Builds the `Client` used for link checking: a short timeout and a small,
+/// bounded redirect policy so one misbehaving link can't hang the whole audit.
+pub fn build_link_checker_client() -> reqwest::Result<Client> {
+    Client::builder()
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .timeout(Duration::from_secs(10))
+        .build()
+}
+
+/// Finds every `<a href>` in `html`, resolves it against `base_url`, and
+/// checks each distinct target concurrently (bounded by `MAX_CONCURRENT_CHECKS`
+/// in-flight requests at a time).
+pub async fn check_links(client: &Client, html: &str, base_url: &Url) -> Vec<LinkCheckResult> {
+    let document = Document::from(html);
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+
+    for link in document.find(Name("a").and(Attr("href", ()))) {
+        if let Some(href) = link.attr("href") {
+            if let Some(resolved) = resolve_href(base_url, href) {
+                if seen.insert(resolved.clone()) {
+                    targets.push((href.to_string(), resolved));
+                }
+            }
+        }
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CHECKS));
+    let mut tasks = Vec::with_capacity(targets.len());
+
+    for (href, resolved_url) in targets {
+        let client = client.clone();
+        let semaphore = Arc::clone(&semaphore);
+        tasks.push(tokio::spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("link checker semaphore should never be closed");
+            check_one_link(&client, href, resolved_url).await
+        }));
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        if let Ok(result) = task.await {
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// Resolves a possibly-relative, possibly protocol-relative `href` against
+/// `base_url`. Returns `None` for links that aren't worth checking at all
+/// (in-page anchors, `mailto:`, `javascript:`, ...).
+fn resolve_href(base_url: &Url, href: &str) -> Option<Url> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') {
+        return None;
+    }
+    if href.starts_with("mailto:") || href.starts_with("tel:") || href.starts_with("javascript:") {
+        return None;
+    }
+    base_url.join(href).ok()
+}
+
+async fn check_one_link(client: &Client, href: String, resolved_url: Url) -> LinkCheckResult {
+    let status = client.head(resolved_url.clone()).send().await;
+
+    let (status, redirect_chain) = match status {
+        Ok(response) => {
+            let final_url = response.url().clone();
+            let chain = if final_url == resolved_url {
+                Vec::new()
+            } else {
+                vec![final_url]
+            };
+            (classify_status(response.status(), &chain), chain)
+        }
+        Err(err) => (classify_error(&err), Vec::new()),
+    };
+
+    LinkCheckResult {
+        href,
+        resolved_url,
+        status,
+        redirect_chain,
+    }
+}
+
+fn classify_status(status: StatusCode, redirect_chain: &[Url]) -> LinkStatus {
+    if status.is_success() {
+        if redirect_chain.is_empty() {
+            LinkStatus::Ok(status)
+        } else {
+            LinkStatus::Redirected {
+                final_status: status,
+            }
+        }
+    } else if status.is_client_error() {
+        LinkStatus::ClientError(status)
+    } else if status.is_server_error() {
+        LinkStatus::ServerError(status)
+    } else {
+        LinkStatus::Other(status.to_string())
+    }
+}
+
+fn classify_error(err: &reqwest::Error) -> LinkStatus {
+    if err.is_timeout() {
+        LinkStatus::Timeout
+    } else if err.is_connect() {
+        LinkStatus::DnsError
+    } else {
+        LinkStatus::Other(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://example.com/blog/post-1").unwrap()
+    }
+
+    #[test]
+    fn resolve_href_cases() {
+        let cases = [
+            ("#top", None),
+            ("", None),
+            ("   ", None),
+            ("mailto:hi@example.com", None),
+            ("tel:+1-555-0100", None),
+            ("javascript:void(0)", None),
+            ("/about", Some("https://example.com/about")),
+            ("../pricing", Some("https://example.com/pricing")),
+            ("page-2", Some("https://example.com/blog/page-2")),
+            ("https://other.com/x", Some("https://other.com/x")),
+            ("//other.com/x", Some("https://other.com/x")),
+        ];
+
+        for (href, expected) in cases {
+            let resolved = resolve_href(&base(), href).map(|url| url.to_string());
+            assert_eq!(resolved.as_deref(), expected, "href = {:?}", href);
+        }
+    }
+
+    #[test]
+    fn classify_status_cases() {
+        let no_chain: Vec<Url> = Vec::new();
+        let chain = vec![Url::parse("https://example.com/final").unwrap()];
+
+        let cases = [
+            (StatusCode::OK, &no_chain, LinkStatus::Ok(StatusCode::OK)),
+            (
+                StatusCode::OK,
+                &chain,
+                LinkStatus::Redirected {
+                    final_status: StatusCode::OK,
+                },
+            ),
+            (
+                StatusCode::NOT_FOUND,
+                &no_chain,
+                LinkStatus::ClientError(StatusCode::NOT_FOUND),
+            ),
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &no_chain,
+                LinkStatus::ServerError(StatusCode::INTERNAL_SERVER_ERROR),
+            ),
+            (
+                StatusCode::MOVED_PERMANENTLY,
+                &no_chain,
+                LinkStatus::Other(StatusCode::MOVED_PERMANENTLY.to_string()),
+            ),
+        ];
+
+        for (status, redirect_chain, expected) in cases {
+            assert_eq!(classify_status(status, redirect_chain), expected, "status = {}", status);
+        }
+    }
+
+    #[test]
+    fn is_broken_matches_classification() {
+        assert!(!LinkStatus::Ok(StatusCode::OK).is_broken());
+        assert!(!LinkStatus::Redirected { final_status: StatusCode::OK }.is_broken());
+        assert!(LinkStatus::ClientError(StatusCode::NOT_FOUND).is_broken());
+        assert!(LinkStatus::ServerError(StatusCode::BAD_GATEWAY).is_broken());
+        assert!(LinkStatus::Timeout.is_broken());
+        assert!(LinkStatus::DnsError.is_broken());
+        assert!(LinkStatus::Other("3 0 2".to_string()).is_broken());
+    }
+}
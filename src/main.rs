@@ -1,26 +1,45 @@
+// Several fields (e.g. `PageContext::document`, `FetchOutcome::Page::status`)
+// exist as context for auditors/crawl consumers that haven't been written
+// yet; this is a single binary crate, so nothing re-exports them to silence
+// the lint the usual way.
+#![allow(dead_code)]
+
 extern crate reqwest;
 extern crate select;
 extern crate regex;
 extern crate chrono;
 extern crate robotparser;
+extern crate tokio;
+extern crate url;
+extern crate serde;
+extern crate serde_json;
+extern crate flate2;
+
+mod analytics;
+mod audit;
+mod crawl;
+mod link_checker;
+mod redirects;
+mod robots;
+mod sitemap;
 
-use reqwest::blocking::Client;
+use reqwest::Client;
 use select::document::Document;
 use select::predicate::{Attr, Name, Predicate};
-use regex::Regex;
 use std::collections::HashMap;
 use std::error::Error;
 use chrono::Utc;
 use std::env;
+use url::Url;
 
-fn fetch_url(client: &Client, url: &str) -> Result<String, Box<dyn Error>> {
-    let res = client.get(url).send()?.text()?;
+async fn fetch_url(client: &Client, url: &str) -> Result<String, Box<dyn Error>> {
+    let res = client.get(url).send().await?.text().await?;
     Ok(res)
 }
 
-fn get_response_time(client: &Client, url: &str) -> Result<u128, Box<dyn Error>> {
+async fn get_response_time(client: &Client, url: &str) -> Result<u128, Box<dyn Error>> {
     let start = Utc::now();
-    client.get(url).send()?;
+    client.get(url).send().await?;
     let duration = Utc::now().signed_duration_since(start).num_milliseconds() as u128;
     Ok(duration)
 }
@@ -47,8 +66,8 @@ fn has_schema_markup(html: &str) -> bool {
     json_ld || microdata || rdfa
 }
 
-fn get_robots_txt(client: &Client, url: &str) -> Option<String> {
-    fetch_url(client, &format!("{}/robots.txt", url)).ok()
+async fn get_robots_txt(client: &Client, url: &str) -> Option<String> {
+    fetch_url(client, &format!("{}/robots.txt", url)).await.ok()
 }
 
 fn is_valid_robots_txt(content: &str) -> bool {
@@ -63,10 +82,6 @@ fn is_valid_robots_txt(content: &str) -> bool {
     valid
 }
 
-fn has_sitemap_xml(client: &Client, url: &str) -> bool {
-    fetch_url(client, &format!("{}/sitemap.xml", url)).is_ok()
-}
-
 fn get_canonical(html: &str) -> Option<String> {
     let document = Document::from(html);
     document.find(Name("link").and(Attr("rel", "canonical")))
@@ -74,21 +89,19 @@ fn get_canonical(html: &str) -> Option<String> {
         .and_then(|n| n.attr("href").map(|href| href.to_string()))
 }
 
-fn get_broken_links(client: &Client, html: &str, base_url: &str) -> (Vec<String>, Vec<String>) {
-    let document = Document::from(html);
+async fn get_broken_links(html: &str, base_url: &Url) -> (Vec<String>, Vec<String>) {
+    let client = match link_checker::build_link_checker_client() {
+        Ok(client) => client,
+        Err(_) => return (Vec::new(), Vec::new()),
+    };
+
+    let results = link_checker::check_links(&client, html, base_url).await;
     let mut broken_links = Vec::new();
     let mut broken_link_pages = Vec::new();
-    for link in document.find(Name("a").and(Attr("href", ()))) {
-        if let Some(href) = link.attr("href") {
-            let url = if href.starts_with('/') {
-                format!("{}{}", base_url, href)
-            } else {
-                href.to_string()
-            };
-            if let Err(_) = client.head(&url).send() {
-                broken_links.push(url.clone());
-                broken_link_pages.push(href.to_string());
-            }
+    for result in results {
+        if result.status.is_broken() {
+            broken_links.push(result.resolved_url.to_string());
+            broken_link_pages.push(result.href);
         }
     }
     (broken_links, broken_link_pages)
@@ -132,11 +145,6 @@ fn is_responsive(html: &str) -> bool {
     document.find(Name("meta").and(Attr("name", "viewport"))).next().is_some()
 }
 
-fn has_google_analytics(html: &str) -> bool {
-    let re = Regex::new(r"UA-\d+-\d+").unwrap();
-    re.is_match(html)
-}
-
 fn is_indexed(html: &str) -> bool {
     let document = Document::from(html);
     if let Some(meta) = document.find(Name("meta").and(Attr("name", "robots"))).next() {
@@ -150,74 +158,189 @@ fn has_search_console(html: &str) -> bool {
     document.find(Name("meta").and(Attr("name", "google-site-verification"))).next().is_some()
 }
 
-fn get_website_details(url: &str) -> HashMap<String, Vec<String>> {
-    let client = Client::new();
+/// Runs every per-page check against an already-fetched page and returns the
+/// structured report the CLI prints (or, with `--format json`, serializes
+/// directly). Shared by the single-URL path (`get_website_details`) and the
+/// site-wide crawler, which calls this once per page it visits.
+pub(crate) async fn audit_page(
+    client: &Client,
+    url: &str,
+    html: &str,
+    site: &audit::SiteContext,
+    redirect_analysis: Option<crate::redirects::RedirectAnalysis>,
+) -> audit::AuditReport {
+    let ctx = audit::build_page_context(client, url, html, site, redirect_analysis).await;
+    let checks = audit::run_checks(&audit::default_auditors(), &ctx);
+
     let mut details = HashMap::new();
 
-    match fetch_url(&client, url) {
+    details.insert("Search Console".to_string(), vec![has_search_console(html).to_string()]);
+    details.insert("Search Console Status".to_string(), vec![if has_search_console(html) { "Present".to_string() } else { "Absent".to_string() }]);
+
+    let base_url = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => Url::parse("about:blank").unwrap(),
+    };
+    let (broken_links, broken_link_pages) = get_broken_links(html, &base_url).await;
+    details.insert("Broken Links".to_string(), broken_links);
+    details.insert("Broken Link Pages".to_string(), broken_link_pages);
+    details.insert("Index Pages".to_string(), vec![if is_indexed(html) { url.to_string() } else { String::new() }]);
+    details.insert("Non Index Pages".to_string(), vec![if !is_indexed(html) { url.to_string() } else { String::new() }]);
+
+    match get_response_time(client, url).await {
+        Ok(time) => {
+            details.insert("Desktop Load Time".to_string(), vec![time.to_string()]);
+            details.insert("Mobile Load Time".to_string(), vec![time.to_string()]);
+            details.insert("Tablet Load Time".to_string(), vec![time.to_string()]);
+            details.insert("Desktop Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
+            details.insert("Mobile Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
+            details.insert("Tablet Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
+            details.insert("Load Time Grade".to_string(), vec![if time < 2000 { "A".to_string() } else if time < 4000 { "B".to_string() } else { "C".to_string() }]);
+        },
+        Err(_) => {
+            details.insert("Desktop Load Time".to_string(), vec!["N/A".to_string()]);
+            details.insert("Mobile Load Time".to_string(), vec!["N/A".to_string()]);
+            details.insert("Tablet Load Time".to_string(), vec!["N/A".to_string()]);
+            details.insert("Desktop Load Time Result".to_string(), vec!["N/A".to_string()]);
+            details.insert("Mobile Load Time Result".to_string(), vec!["N/A".to_string()]);
+            details.insert("Tablet Load Time Result".to_string(), vec!["N/A".to_string()]);
+            details.insert("Load Time Grade".to_string(), vec!["N/A".to_string()]);
+        }
+    }
+
+    audit::AuditReport {
+        url: url.to_string(),
+        checks,
+        details,
+    }
+}
+
+async fn get_website_details(url: &str) -> audit::AuditReport {
+    let client = Client::new();
+
+    match fetch_url(&client, url).await {
         Ok(html) => {
-            details.insert("Schema Markup".to_string(), vec![if has_schema_markup(&html) { "Found".to_string() } else { "Not Found".to_string() }]);
-            
-            if let Some(robots_txt) = get_robots_txt(&client, url) {
-                details.insert("Robots.txt".to_string(), vec![robots_txt.clone()]);
-                details.insert("Robots.txt Status".to_string(), vec![if is_valid_robots_txt(&robots_txt) { "Valid".to_string() } else { "Invalid".to_string() }]);
-            } else {
-                details.insert("Robots.txt".to_string(), vec!["Not Found".to_string()]);
-                details.insert("Robots.txt Status".to_string(), vec!["Not Found".to_string()]);
-            }
-            
-            details.insert("Sitemap.xml".to_string(), vec![if has_sitemap_xml(&client, url) { "Found".to_string() } else { "Not Found".to_string() }]);
-            details.insert("Canonical Tags".to_string(), vec![get_canonical(&html).unwrap_or_default()]);
-            details.insert("AMP".to_string(), vec![has_amp(&html).to_string()]);
-            details.insert("Responsive".to_string(), vec![is_responsive(&html).to_string()]);
-            details.insert("Google Analytics".to_string(), vec![has_google_analytics(&html).to_string()]);
-            details.insert("Search Console".to_string(), vec![has_search_console(&html).to_string()]);
-            details.insert("Search Console Status".to_string(), vec![if details.get("Search Console").unwrap().contains(&"true".to_string()) { "Present".to_string() } else { "Absent".to_string() }]);
-            
-            let (broken_links, broken_link_pages) = get_broken_links(&client, &html, url);
-            details.insert("Broken Links".to_string(), broken_links);
-            details.insert("Broken Link Pages".to_string(), broken_link_pages);
-            details.insert("Index Pages".to_string(), vec![if is_indexed(&html) { url.to_string() } else { String::new() }]);
-            details.insert("Non Index Pages".to_string(), vec![if !is_indexed(&html) { url.to_string() } else { String::new() }]);
-
-            match get_response_time(&client, url) {
-                Ok(time) => {
-                    details.insert("Desktop Load Time".to_string(), vec![time.to_string()]);
-                    details.insert("Mobile Load Time".to_string(), vec![time.to_string()]);
-                    details.insert("Tablet Load Time".to_string(), vec![time.to_string()]);
-                    details.insert("Desktop Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
-                    details.insert("Mobile Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
-                    details.insert("Tablet Load Time Result".to_string(), vec![if time < 2000 { "Good".to_string() } else if time < 4000 { "Moderate".to_string() } else { "Poor".to_string() }]);
-                    details.insert("Load Time Grade".to_string(), vec![if time < 2000 { "A".to_string() } else if time < 4000 { "B".to_string() } else { "C".to_string() }]);
-                },
-                Err(_) => {
-                    details.insert("Desktop Load Time".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Mobile Load Time".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Tablet Load Time".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Desktop Load Time Result".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Mobile Load Time Result".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Tablet Load Time Result".to_string(), vec!["N/A".to_string()]);
-                    details.insert("Load Time Grade".to_string(), vec!["N/A".to_string()]);
-                }
-            }
+            let site = audit::SiteContext::for_site(&client, url).await;
+            let redirect_analysis = audit::analyze_redirects_fresh(url).await;
+            audit_page(&client, url, &html, &site, redirect_analysis).await
         }
         Err(_) => {
+            let mut details = HashMap::new();
             details.insert("error".to_string(), vec!["Failed to retrieve website content".to_string()]);
+            audit::AuditReport {
+                url: url.to_string(),
+                checks: Vec::new(),
+                details,
+            }
         }
     }
-
-    details
 }
 
-fn main() {
+#[tokio::main]
+async fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <url>", args[0]);
+        eprintln!(
+            "Usage: {} <url> [--crawl] [--max-pages N] [--max-depth N] [--format json|text]",
+            args[0]
+        );
         return;
     }
     let url = &args[1];
-    let website_details = get_website_details(url);
-    for (key, value) in website_details.iter() {
+    let format = json_requested(&args);
+
+    if args.iter().any(|arg| arg == "--crawl") {
+        run_crawl(url, &args[2..], format).await;
+        return;
+    }
+
+    let report = get_website_details(url).await;
+    print_report(&report, format);
+}
+
+/// Whether `--format json` was passed; anything else (including no flag at
+/// all) keeps the human-readable output that's always been the default.
+fn json_requested(args: &[String]) -> bool {
+    args.windows(2)
+        .any(|pair| pair[0] == "--format" && pair[1] == "json")
+}
+
+fn print_report(report: &audit::AuditReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(body) => println!("{}", body),
+            Err(err) => eprintln!("Failed to serialize report: {}", err),
+        }
+        return;
+    }
+
+    println!("== {} ==", report.url);
+    for check in &report.checks {
+        println!("{:?} [{:?}] {}", check.id, check.severity, check.detail);
+    }
+    for (key, value) in report.details.iter() {
         println!("{}: {:?}", key, value);
     }
 }
+
+async fn run_crawl(url: &str, flags: &[String], format_json: bool) {
+    let start_url = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("Invalid URL {}: {}", url, err);
+            return;
+        }
+    };
+
+    let mut config = crawl::CrawlConfig::default();
+    for pair in flags.windows(2) {
+        match pair[0].as_str() {
+            "--max-pages" => {
+                if let Ok(value) = pair[1].parse() {
+                    config.max_pages = value;
+                }
+            }
+            "--max-depth" => {
+                if let Ok(value) = pair[1].parse() {
+                    config.max_depth = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let client = match crawl::build_crawl_client() {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("Failed to build crawl client: {}", err);
+            return;
+        }
+    };
+
+    let report = crawl::crawl_site(&client, &start_url, &config).await;
+
+    println!(
+        "Crawled {} page(s) (too large: {}, timed out: {}, failed: {}, disallowed by robots.txt: {})",
+        report.pages.len(),
+        report.pages_skipped_too_large,
+        report.pages_timed_out,
+        report.pages_failed,
+        report.pages_disallowed_by_robots
+    );
+    for page in &report.pages {
+        print_report(&page.details, format_json);
+    }
+
+    if !report.sitemap_coverage.orphaned_from_crawl.is_empty() {
+        println!("\nIn sitemap but never reached by the crawl:");
+        for url in &report.sitemap_coverage.orphaned_from_crawl {
+            println!("  {}", url);
+        }
+    }
+    if !report.sitemap_coverage.missing_from_sitemap.is_empty() {
+        println!("\nCrawled and indexable but missing from the sitemap:");
+        for url in &report.sitemap_coverage.missing_from_sitemap {
+            println!("  {}", url);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode};
+use select::document::Document;
+use select::predicate::{Name, Predicate};
+use url::Url;
+
+/// Real redirect chains are rarely more than a couple of hops; past this we
+/// assume a loop and give up rather than hang the audit.
+const MAX_HOPS: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct RedirectHop {
+    pub url: Url,
+    pub status: StatusCode,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedirectAnalysis {
+    pub hops: Vec<RedirectHop>,
+    pub final_url: Url,
+    pub upgraded_to_https: bool,
+    /// `http://` `src`/`href` references found on an otherwise `https` final
+    /// page.
+    pub mixed_content: Vec<String>,
+}
+
+impl RedirectAnalysis {
+    pub fn hop_count(&self) -> usize {
+        self.hops.len().saturating_sub(1)
+    }
+
+    pub fn has_long_chain(&self) -> bool {
+        self.hop_count() > 1
+    }
+}
+
+/// Builds the client used to walk redirect chains. Redirects are turned off
+/// deliberately: following them automatically is exactly what hides the
+/// chain we're trying to report on.
+pub fn build_redirect_client() -> reqwest::Result<Client> {
+    Client::builder()
+        .redirect(Policy::none())
+        .timeout(Duration::from_secs(10))
+        .build()
+}
+
+/// Walks the redirect chain starting at `start_url` hop by hop, then scans
+/// the final page's HTML for mixed content if it ended up on `https`.
+pub async fn analyze(client: &Client, start_url: &Url) -> Result<RedirectAnalysis, String> {
+    let mut hops = Vec::new();
+    let mut current = start_url.clone();
+
+    let final_html = loop {
+        if hops.len() >= MAX_HOPS {
+            return Err("too many redirects".to_string());
+        }
+
+        let response = client
+            .get(current.clone())
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+        let status = response.status();
+        hops.push(RedirectHop {
+            url: current.clone(),
+            status,
+        });
+
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| "redirect without a usable Location header".to_string())?;
+            current = current.join(location).map_err(|err| err.to_string())?;
+            continue;
+        }
+
+        break response.text().await.map_err(|err| err.to_string())?;
+    };
+
+    Ok(finish_analysis(hops, start_url.scheme(), current, &final_html))
+}
+
+/// Builds a [`RedirectAnalysis`] from a chain of hops (the last of which is
+/// the final, non-redirecting response) plus the scheme the chain started
+/// on. Shared between walking a chain directly ([`analyze`]) and
+/// reconstructing one from hops the crawler already observed, so both paths
+/// agree on what counts as an HTTP→HTTPS upgrade and when to bother scanning
+/// for mixed content.
+pub(crate) fn finish_analysis(hops: Vec<RedirectHop>, start_scheme: &str, final_url: Url, final_html: &str) -> RedirectAnalysis {
+    let upgraded_to_https = start_scheme == "http" && final_url.scheme() == "https";
+    let mixed_content = if final_url.scheme() == "https" {
+        find_mixed_content(final_html)
+    } else {
+        Vec::new()
+    };
+
+    RedirectAnalysis {
+        hops,
+        final_url,
+        upgraded_to_https,
+        mixed_content,
+    }
+}
+
+/// Finds `http://` `src`/`href` references among the subresources a page
+/// commonly loads.
+pub(crate) fn find_mixed_content(html: &str) -> Vec<String> {
+    let document = Document::from(html);
+    let mut found = Vec::new();
+
+    for node in document.find(Name("script").or(Name("img")).or(Name("link")).or(Name("iframe"))) {
+        for attr_name in ["src", "href"] {
+            if let Some(value) = node.attr(attr_name) {
+                if value.starts_with("http://") {
+                    found.push(value.to_string());
+                }
+            }
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hop(url: &str, status: StatusCode) -> RedirectHop {
+        RedirectHop { url: Url::parse(url).unwrap(), status }
+    }
+
+    #[test]
+    fn find_mixed_content_cases() {
+        let cases = [
+            ("<html></html>", vec![]),
+            (r#"<img src="https://example.com/a.png">"#, vec![]),
+            (r#"<img src="http://example.com/a.png">"#, vec!["http://example.com/a.png"]),
+            (r#"<script src="http://example.com/a.js"></script>"#, vec!["http://example.com/a.js"]),
+            (r#"<link href="http://example.com/a.css">"#, vec!["http://example.com/a.css"]),
+            (r#"<iframe src="http://example.com/embed"></iframe>"#, vec!["http://example.com/embed"]),
+            (r#"<a href="http://example.com/page">link</a>"#, vec![]),
+            (
+                r#"<img src="http://a.example.com/1.png"><script src="http://a.example.com/2.js"></script>"#,
+                vec!["http://a.example.com/1.png", "http://a.example.com/2.js"],
+            ),
+        ];
+
+        for (html, expected) in cases {
+            assert_eq!(find_mixed_content(html), expected, "html = {:?}", html);
+        }
+    }
+
+    #[test]
+    fn hop_count_and_has_long_chain() {
+        let single_hop = RedirectAnalysis {
+            hops: vec![hop("https://example.com/", StatusCode::OK)],
+            final_url: Url::parse("https://example.com/").unwrap(),
+            upgraded_to_https: false,
+            mixed_content: Vec::new(),
+        };
+        assert_eq!(single_hop.hop_count(), 0);
+        assert!(!single_hop.has_long_chain());
+
+        let one_redirect = RedirectAnalysis {
+            hops: vec![
+                hop("http://example.com/old", StatusCode::MOVED_PERMANENTLY),
+                hop("http://example.com/new", StatusCode::OK),
+            ],
+            final_url: Url::parse("http://example.com/new").unwrap(),
+            upgraded_to_https: false,
+            mixed_content: Vec::new(),
+        };
+        assert_eq!(one_redirect.hop_count(), 1);
+        assert!(!one_redirect.has_long_chain());
+
+        let long_chain = RedirectAnalysis {
+            hops: vec![
+                hop("http://example.com/a", StatusCode::MOVED_PERMANENTLY),
+                hop("http://example.com/b", StatusCode::FOUND),
+                hop("http://example.com/c", StatusCode::OK),
+            ],
+            final_url: Url::parse("http://example.com/c").unwrap(),
+            upgraded_to_https: false,
+            mixed_content: Vec::new(),
+        };
+        assert_eq!(long_chain.hop_count(), 2);
+        assert!(long_chain.has_long_chain());
+    }
+
+    #[test]
+    fn finish_analysis_detects_https_upgrade() {
+        let hops = vec![
+            hop("http://example.com/", StatusCode::MOVED_PERMANENTLY),
+            hop("https://example.com/", StatusCode::OK),
+        ];
+        let analysis = finish_analysis(hops, "http", Url::parse("https://example.com/").unwrap(), "<html></html>");
+        assert!(analysis.upgraded_to_https);
+    }
+
+    #[test]
+    fn finish_analysis_no_upgrade_when_already_https() {
+        let hops = vec![hop("https://example.com/", StatusCode::OK)];
+        let analysis = finish_analysis(hops, "https", Url::parse("https://example.com/").unwrap(), "<html></html>");
+        assert!(!analysis.upgraded_to_https);
+    }
+
+    #[test]
+    fn finish_analysis_scans_mixed_content_only_on_https_final_page() {
+        let html = r#"<img src="http://insecure.example.com/x.png">"#;
+
+        let https_final = finish_analysis(
+            vec![hop("https://example.com/", StatusCode::OK)],
+            "https",
+            Url::parse("https://example.com/").unwrap(),
+            html,
+        );
+        assert_eq!(https_final.mixed_content, vec!["http://insecure.example.com/x.png"]);
+
+        let http_final = finish_analysis(
+            vec![hop("http://example.com/", StatusCode::OK)],
+            "http",
+            Url::parse("http://example.com/").unwrap(),
+            html,
+        );
+        assert!(http_final.mixed_content.is_empty());
+    }
+}
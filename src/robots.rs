@@ -0,0 +1,122 @@
+use robotparser::RobotFileParser;
+use select::document::Document;
+use select::predicate::{Attr, Name, Predicate};
+use url::Url;
+
+/// The user-agent token the crawler identifies itself as, both when asking
+/// `robots.txt` for permission and when reading per-UA rules in a meta tag.
+pub const USER_AGENT: &str = "web_audit_rust";
+
+/// Wraps a site's parsed `robots.txt` so the crawler can ask "am I allowed to
+/// fetch this?" before every request. Reading `robots.txt` itself is
+/// blocking I/O under the hood (that's how the `robotparser` crate is
+/// built), so it's done once per crawl on a blocking thread rather than per
+/// request.
+pub struct RobotsGate {
+    parser: RobotFileParser<'static>,
+}
+
+impl RobotsGate {
+    /// Fetches and parses `/robots.txt` for `base_url`. If it can't be
+    /// fetched or parsed, the gate allows everything (matching how browsers
+    /// and most crawlers treat a missing robots.txt).
+    pub async fn for_site(base_url: &Url) -> Self {
+        let robots_url = base_url
+            .join("/robots.txt")
+            .unwrap_or_else(|_| base_url.clone());
+        let parser = RobotFileParser::new(robots_url.as_str());
+
+        // `read()` mutates the parser's internal (`Cell`/`RefCell`) state in
+        // place, so the parser that comes back out of `spawn_blocking` has
+        // to be the same one `read()` ran on — reading a clone and keeping
+        // the original would silently leave every lookup unread, and an
+        // unread parser denies everything (see `can_fetch`'s `last_checked`
+        // guard), not allows it.
+        let parser = tokio::task::spawn_blocking(move || {
+            parser.read();
+            parser
+        })
+        .await
+        .unwrap_or_else(|_| RobotFileParser::new(robots_url.as_str()));
+
+        RobotsGate { parser }
+    }
+
+    /// A gate that allows everything, because `robots.txt` was never read
+    /// (timed out, or the site couldn't be reached at all). Relies on the
+    /// same fail-open `mtime() == 0` path as [`is_allowed`](Self::is_allowed).
+    pub fn allow_all(base_url: &Url) -> Self {
+        let robots_url = base_url
+            .join("/robots.txt")
+            .unwrap_or_else(|_| base_url.clone());
+        RobotsGate {
+            parser: RobotFileParser::new(robots_url.as_str()),
+        }
+    }
+
+    /// Whether `url` may be fetched under the rules for [`USER_AGENT`].
+    pub fn is_allowed(&self, url: &Url) -> bool {
+        // `can_fetch` denies everything until `robots.txt` has actually been
+        // read (`mtime() == 0`) — the opposite of this gate's documented
+        // fail-open contract. That guard only exists to stop `can_fetch`
+        // being asked before `read()` has run; here it also covers the
+        // `for_site` fallback where `read()` never got a chance to run at
+        // all, which should allow everything, not deny it.
+        if self.parser.mtime() == 0 {
+            return true;
+        }
+        self.parser.can_fetch(USER_AGENT, url.as_str())
+    }
+}
+
+/// What a page's own markup says about how it should be treated, combining
+/// `<meta name="robots">` and the `X-Robots-Tag` response header (the header
+/// takes precedence in practice, but either can set either directive).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RobotsDirectives {
+    pub noindex: bool,
+    pub nofollow: bool,
+}
+
+impl RobotsDirectives {
+    pub fn merge(self, other: RobotsDirectives) -> RobotsDirectives {
+        RobotsDirectives {
+            noindex: self.noindex || other.noindex,
+            nofollow: self.nofollow || other.nofollow,
+        }
+    }
+}
+
+/// Parses the directives out of a page's `<meta name="robots">` tag.
+pub fn meta_robots_directives(html: &str) -> RobotsDirectives {
+    let document = Document::from(html);
+    let content = document
+        .find(Name("meta").and(Attr("name", "robots")))
+        .next()
+        .and_then(|node| node.attr("content"))
+        .unwrap_or("")
+        .to_lowercase();
+
+    directives_from_content(&content)
+}
+
+/// Parses the same directive vocabulary out of an `X-Robots-Tag` header
+/// value.
+pub fn header_robots_directives(x_robots_tag: Option<&str>) -> RobotsDirectives {
+    match x_robots_tag {
+        Some(value) => directives_from_content(&value.to_lowercase()),
+        None => RobotsDirectives::default(),
+    }
+}
+
+fn directives_from_content(content: &str) -> RobotsDirectives {
+    // "none" is shorthand for "noindex, nofollow" (and is a comma-separated
+    // directive in its own right, same as the others), so it needs the same
+    // `contains` treatment rather than an exact match against the whole
+    // attribute value.
+    let none = content.contains("none");
+    RobotsDirectives {
+        noindex: none || content.contains("noindex"),
+        nofollow: none || content.contains("nofollow"),
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use reqwest::Client;
+use select::document::Document;
+use select::predicate::Name;
+use url::Url;
+
+/// `<sitemapindex>` trees are rarely more than one level deep in practice;
+/// this just guards against a misconfigured site pointing sitemaps at each
+/// other forever.
+const MAX_SITEMAP_INDEX_DEPTH: usize = 3;
+
+/// Discovers the sitemap(s) advertised for a site: prefers `Sitemap:` lines
+/// from `robots.txt`, falling back to the conventional `/sitemap.xml` path
+/// when none are listed.
+pub fn discover_sitemap_locations(base_url: &Url, robots_txt: Option<&str>) -> Vec<Url> {
+    let mut locations: Vec<Url> = robots_txt
+        .map(sitemap_lines_from_robots_txt)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|loc| Url::parse(loc).ok())
+        .collect();
+
+    if locations.is_empty() {
+        if let Ok(default_location) = base_url.join("/sitemap.xml") {
+            locations.push(default_location);
+        }
+    }
+
+    locations
+}
+
+fn sitemap_lines_from_robots_txt(robots_txt: &str) -> Vec<String> {
+    robots_txt
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("Sitemap:")
+                .or_else(|| line.strip_prefix("sitemap:"))
+        })
+        .map(|value| value.trim().to_string())
+        .collect()
+}
+
+/// Fetches every sitemap reachable from `locations`, transparently expanding
+/// `<sitemapindex>` entries and decompressing `.xml.gz` bodies, and returns
+/// the full set of page URLs (`<loc>` entries under a `<urlset>`) found
+/// across all of them.
+pub async fn fetch_all_sitemap_urls(client: &Client, locations: Vec<Url>) -> Vec<Url> {
+    let mut seen_sitemaps = HashSet::new();
+    let mut queue: VecDeque<(Url, usize)> = locations.into_iter().map(|url| (url, 0)).collect();
+    let mut page_urls = Vec::new();
+
+    while let Some((sitemap_url, depth)) = queue.pop_front() {
+        if depth > MAX_SITEMAP_INDEX_DEPTH || !seen_sitemaps.insert(sitemap_url.to_string()) {
+            continue;
+        }
+
+        let body = match fetch_sitemap_body(client, &sitemap_url).await {
+            Some(body) => body,
+            None => continue,
+        };
+
+        let (is_index, locs) = parse_sitemap_body(&body);
+        for loc in locs {
+            let url = match Url::parse(&loc) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+            if is_index {
+                queue.push_back((url, depth + 1));
+            } else {
+                page_urls.push(url);
+            }
+        }
+    }
+
+    page_urls
+}
+
+/// Parses a sitemap (or sitemap index) body into whether it's an index and
+/// the `<loc>` entries it lists. A `<sitemapindex>`'s `<loc>`s point at more
+/// sitemaps to fetch; a `<urlset>`'s point at actual pages.
+fn parse_sitemap_body(body: &str) -> (bool, Vec<String>) {
+    let document = Document::from(body);
+    let is_index = document.find(Name("sitemapindex")).next().is_some();
+
+    let locs = document
+        .find(Name("loc"))
+        .map(|node| node.text().trim().to_string())
+        .filter(|loc| !loc.is_empty())
+        .collect();
+
+    (is_index, locs)
+}
+
+/// Fetches a sitemap body, transparently gunzipping it if the URL ends in
+/// `.gz` or the response is served as `Content-Type: ...gzip`.
+async fn fetch_sitemap_body(client: &Client, url: &Url) -> Option<String> {
+    let response = client.get(url.clone()).send().await.ok()?;
+    let is_gzip = url.path().ends_with(".gz")
+        || response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.contains("gzip"))
+            .unwrap_or(false);
+
+    let bytes = response.bytes().await.ok()?;
+    decode_body(&bytes, is_gzip)
+}
+
+/// Decodes a sitemap response body, gunzipping first if `is_gzip`.
+fn decode_body(bytes: &[u8], is_gzip: bool) -> Option<String> {
+    if is_gzip {
+        let mut decoder = GzDecoder::new(bytes);
+        let mut decompressed = String::new();
+        decoder.read_to_string(&mut decompressed).ok()?;
+        Some(decompressed)
+    } else {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+/// Coverage diagnostics comparing what the sitemap lists against what the
+/// crawl actually reached: both directions are genuinely useful SEO
+/// findings on their own.
+#[derive(Debug, Default)]
+pub struct SitemapCoverage {
+    /// Listed in the sitemap but never reached by the crawl.
+    pub orphaned_from_crawl: Vec<Url>,
+    /// Crawled, indexable, but missing from the sitemap.
+    pub missing_from_sitemap: Vec<Url>,
+}
+
+pub fn diff_coverage(sitemap_urls: &[Url], crawled_indexable_urls: &[Url]) -> SitemapCoverage {
+    let sitemap_set: HashSet<String> = sitemap_urls.iter().map(normalize).collect();
+    let crawled_set: HashSet<String> = crawled_indexable_urls.iter().map(normalize).collect();
+
+    SitemapCoverage {
+        orphaned_from_crawl: sitemap_urls
+            .iter()
+            .filter(|url| !crawled_set.contains(&normalize(url)))
+            .cloned()
+            .collect(),
+        missing_from_sitemap: crawled_indexable_urls
+            .iter()
+            .filter(|url| !sitemap_set.contains(&normalize(url)))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn normalize(url: &Url) -> String {
+    let mut normalized = url.clone();
+    normalized.set_fragment(None);
+    let s = normalized.as_str();
+    s.strip_suffix('/').unwrap_or(s).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn sitemap_lines_from_robots_txt_cases() {
+        let cases = [
+            ("Sitemap: https://example.com/sitemap.xml", vec!["https://example.com/sitemap.xml"]),
+            ("sitemap: https://example.com/lower.xml", vec!["https://example.com/lower.xml"]),
+            (
+                "User-agent: *\nDisallow: /admin\nSitemap: https://example.com/a.xml\nSitemap: https://example.com/b.xml",
+                vec!["https://example.com/a.xml", "https://example.com/b.xml"],
+            ),
+            ("User-agent: *\nDisallow: /", vec![]),
+        ];
+
+        for (robots_txt, expected) in cases {
+            let lines = sitemap_lines_from_robots_txt(robots_txt);
+            assert_eq!(lines, expected, "robots_txt = {:?}", robots_txt);
+        }
+    }
+
+    #[test]
+    fn discover_sitemap_locations_prefers_robots_txt() {
+        let base = Url::parse("https://example.com/").unwrap();
+
+        let from_robots = discover_sitemap_locations(&base, Some("Sitemap: https://example.com/custom.xml"));
+        assert_eq!(from_robots, vec![Url::parse("https://example.com/custom.xml").unwrap()]);
+
+        let fallback = discover_sitemap_locations(&base, Some("User-agent: *"));
+        assert_eq!(fallback, vec![Url::parse("https://example.com/sitemap.xml").unwrap()]);
+
+        let no_robots = discover_sitemap_locations(&base, None);
+        assert_eq!(no_robots, vec![Url::parse("https://example.com/sitemap.xml").unwrap()]);
+    }
+
+    #[test]
+    fn parse_sitemap_body_urlset() {
+        let body = r#"<?xml version="1.0"?>
+            <urlset><url><loc>https://example.com/a</loc></url>
+            <url><loc>https://example.com/b</loc></url></urlset>"#;
+
+        let (is_index, locs) = parse_sitemap_body(body);
+        assert!(!is_index);
+        assert_eq!(locs, vec!["https://example.com/a", "https://example.com/b"]);
+    }
+
+    #[test]
+    fn parse_sitemap_body_sitemapindex() {
+        let body = r#"<?xml version="1.0"?>
+            <sitemapindex><sitemap><loc>https://example.com/sitemap-1.xml</loc></sitemap></sitemapindex>"#;
+
+        let (is_index, locs) = parse_sitemap_body(body);
+        assert!(is_index);
+        assert_eq!(locs, vec!["https://example.com/sitemap-1.xml"]);
+    }
+
+    #[test]
+    fn parse_sitemap_body_ignores_blank_locs() {
+        let body = r#"<urlset><url><loc>  </loc></url><url><loc>https://example.com/a</loc></url></urlset>"#;
+
+        let (_, locs) = parse_sitemap_body(body);
+        assert_eq!(locs, vec!["https://example.com/a"]);
+    }
+
+    #[test]
+    fn decode_body_plain_text() {
+        let decoded = decode_body(b"<urlset></urlset>", false).unwrap();
+        assert_eq!(decoded, "<urlset></urlset>");
+    }
+
+    #[test]
+    fn decode_body_gzip() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"<urlset><url><loc>https://example.com/a</loc></url></urlset>").unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let decoded = decode_body(&gzipped, true).unwrap();
+        assert_eq!(decoded, "<urlset><url><loc>https://example.com/a</loc></url></urlset>");
+    }
+
+    #[test]
+    fn decode_body_invalid_gzip_returns_none() {
+        assert!(decode_body(b"not actually gzip", true).is_none());
+    }
+
+    #[test]
+    fn diff_coverage_cases() {
+        let sitemap_urls = vec![
+            Url::parse("https://example.com/a").unwrap(),
+            Url::parse("https://example.com/b/").unwrap(),
+        ];
+        let crawled_indexable_urls = vec![
+            Url::parse("https://example.com/a").unwrap(),
+            Url::parse("https://example.com/c").unwrap(),
+        ];
+
+        let coverage = diff_coverage(&sitemap_urls, &crawled_indexable_urls);
+        assert_eq!(coverage.orphaned_from_crawl, vec![Url::parse("https://example.com/b/").unwrap()]);
+        assert_eq!(coverage.missing_from_sitemap, vec![Url::parse("https://example.com/c").unwrap()]);
+    }
+}